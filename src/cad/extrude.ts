@@ -44,7 +44,7 @@ export function extrudeSolidFeature(part: Pick<PartDocument, "features">, featur
 		throw new Error(`Profile "${feature.target.profileId}" does not contain any valid closed loops.`)
 	}
 
-	const solidId = options.solidId ?? `${feature.id}-solid`
+	const solidId = options.solidId ?? getDefaultExtrudeSolidId(feature.id)
 	const startOffset = options.startOffset ?? 0
 	const endOffset = startOffset + feature.depth
 	const frame = resolveSketchTargetFrame(part, materializedSketch.target)
@@ -133,6 +133,10 @@ export function extrudeSolidFeature(part: Pick<PartDocument, "features">, featur
 	}
 }
 
+export function getDefaultExtrudeSolidId(featureId: string): string {
+	return `${featureId}-solid`
+}
+
 export function resolveSketchTargetFrame(part: Pick<PartDocument, "features">, target: SketchTarget): SketchFrame3D {
 	if (target.type === "plane") {
 		return getPlaneSketchFrame(target.plane)
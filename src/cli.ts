@@ -1,14 +1,14 @@
 #!/usr/bin/env bun
 
-import { mkdir, readFile, stat, writeFile } from "node:fs/promises"
+import { mkdir, readFile, readdir, stat, writeFile } from "node:fs/promises"
 import { homedir, platform } from "node:os"
 import { dirname, join, resolve } from "node:path"
-import { extrudeSolidFeature } from "./cad/extrude"
+import { extrudeSolidFeature, getDefaultExtrudeSolidId } from "./cad/extrude"
 import type { Project, ProjectDocument, ProjectDocumentType, ProjectNode } from "./contract"
 import { PCadPart, PuppyCadClient } from "./pcad/project"
 import { applySyncedProjectCommands, type CadCommand, type SyncedProjectCommand } from "./project-commands"
 import { createProjectFile, normalizeProjectFile, serializeProjectFile } from "./project-file"
-import { renderProjectPreviewPng, type RenderLabel } from "./render"
+import { renderProjectPreviewPng, type RenderLabel, type RenderProjection } from "./render"
 import type { PartDocument, PartFeature, SketchDimension, SketchEntity, SketchPlane, Solid, SolidEdge, SolidFace, SolidVertex } from "./schema"
 import type { Vector3D } from "./types"
 
@@ -44,6 +44,13 @@ type CliContext = {
 	renderPng: CliRenderPng
 }
 
+type CliErrorCategory = "failure" | "io" | "parse" | "semantic" | "render"
+
+type CliDiagnostic = {
+	category: CliErrorCategory
+	message: string
+}
+
 type GlobalCliOptions = {
 	serverUrl?: string
 	projectId?: string
@@ -69,6 +76,16 @@ type InspectOptions = {
 	json: boolean
 }
 
+type ValidateOptions = InspectOptions & {
+	dir?: string
+}
+
+type ValidationResult = {
+	file: string
+	ok: boolean
+	diagnostics: CliDiagnostic[]
+}
+
 type ProjectStats = {
 	documents: number
 	folders: number
@@ -118,9 +135,12 @@ type CliGeometry = {
 	errors: { partId: string; featureId: string; message: string }[]
 }
 
+type FeatureSelection = ReadonlyMap<string, ReadonlySet<string>>
+
 type QueryArgs = {
 	target?: string
 	bodyId?: string
+	featureId?: string
 }
 
 type RenderOptions = {
@@ -128,6 +148,10 @@ type RenderOptions = {
 	outPath: string
 	width?: number
 	height?: number
+	background?: [number, number, number, number]
+	projection?: RenderProjection
+	only?: string[]
+	timeoutMs?: number
 	showDimensions?: boolean
 }
 
@@ -180,6 +204,13 @@ const DEFAULT_VERSION = "0.1.0"
 const DEFAULT_PROJECT_FILE = "puppycad.pcad"
 const DEFAULT_SERVER_URL = "http://localhost:5337"
 const DOCUMENT_TYPES: ProjectDocumentType[] = ["schemantic", "pcb", "part", "assembly", "diagram"]
+const CLI_EXIT_CODES: Record<CliErrorCategory, number> = {
+	failure: 1,
+	io: 2,
+	parse: 3,
+	semantic: 4,
+	render: 5
+}
 
 export async function runPuppycadCli(args: readonly string[], options: CliOptions = {}): Promise<number> {
 	const output = options.output ?? {
@@ -237,7 +268,7 @@ export async function runPuppycadCli(args: readonly string[], options: CliOption
 			return await runInspectCommand(rest, context)
 		}
 		if (command === "validate") {
-			return await runFileInspect(parseInspectArgs(rest), context.cwd, context.output)
+			return await runValidateCommand(rest, context)
 		}
 		if (command === "init" || command === "create") {
 			return await runInit(parseInitArgs(rest), context.cwd, context.output)
@@ -250,8 +281,9 @@ export async function runPuppycadCli(args: readonly string[], options: CliOption
 			output.stdout(error.message)
 			return 0
 		}
-		output.stderr(error instanceof Error ? error.message : String(error))
-		return 1
+		const diagnostic = toCliDiagnostic(error)
+		output.stderr(context.globals.json ? JSON.stringify({ ok: false, diagnostics: [diagnostic] }, null, 2) : diagnostic.message)
+		return CLI_EXIT_CODES[diagnostic.category]
 	}
 }
 
@@ -368,6 +400,47 @@ function parseInspectArgs(args: readonly string[]): InspectOptions {
 	return { target, json }
 }
 
+function parseValidateArgs(args: readonly string[]): ValidateOptions {
+	let target: string | undefined
+	let dir: string | undefined
+	let json = false
+
+	for (let index = 0; index < args.length; index += 1) {
+		const arg = args[index]
+		if (!arg) {
+			continue
+		}
+		if (arg === "--help" || arg === "-h") {
+			throw new CliHelpError(formatValidateHelp())
+		}
+		if (arg === "--json") {
+			json = true
+			continue
+		}
+		if (arg === "--dir") {
+			dir = readOptionValue(args, index, arg)
+			index += 1
+			continue
+		}
+		if (arg.startsWith("--dir=")) {
+			dir = arg.slice("--dir=".length)
+			continue
+		}
+		if (arg.startsWith("-")) {
+			throw new Error(`Unknown validate option: ${arg}`)
+		}
+		if (target) {
+			throw new Error(`Unexpected validate argument: ${arg}`)
+		}
+		target = arg
+	}
+	if (target && dir) {
+		throw new Error("Use either a project file or --dir, not both.")
+	}
+
+	return { json, ...(target ? { target } : {}), ...(dir ? { dir } : {}) }
+}
+
 async function runConfigCommand(args: readonly string[], context: CliContext): Promise<number> {
 	const [action, ...rest] = args
 	if (!action || action === "--help" || action === "-h") {
@@ -452,7 +525,7 @@ async function runDoctor(context: CliContext): Promise<number> {
 		} else {
 			context.output.stderr(`Unable to reach Puppycad server at ${serverUrl}: ${formatFileError(error)}`)
 		}
-		return 1
+		return CLI_EXIT_CODES.io
 	}
 }
 
@@ -522,6 +595,25 @@ async function runInspectCommand(args: readonly string[], context: CliContext):
 	return await runServerInspect(options.target, { ...context, globals: { ...context.globals, json } })
 }
 
+async function runValidateCommand(args: readonly string[], context: CliContext): Promise<number> {
+	const json = context.globals.json || args.includes("--json")
+	try {
+		const options = parseValidateArgs(args)
+		if (options.dir) {
+			return await runValidateDirectory(options.dir, json, context.cwd, context.output)
+		}
+		if (json) {
+			return await runValidateFileJson(options.target, context.cwd, context.output)
+		}
+		return await runFileInspect({ ...(options.target ? { target: options.target } : {}), json }, context.cwd, context.output)
+	} catch (error) {
+		if (!json || error instanceof CliHelpError) {
+			throw error
+		}
+		return writeValidateError(error, context.output)
+	}
+}
+
 async function runCadCommand(args: readonly string[], context: CliContext): Promise<number> {
 	const [area, action, ...rest] = args
 	if (!area || area === "--help" || area === "-h") {
@@ -604,11 +696,20 @@ async function runQueryCommand(args: readonly string[], context: CliContext): Pr
 		context.output.stdout(formatQueryHelp())
 		return 0
 	}
-	if (!["features", "geometry", "bodies", "faces", "edges", "bbox"].includes(query)) {
+	if (!["features", "geometry", "bodies", "faces", "edges", "bbox", "refs"].includes(query)) {
 		throw new Error(`Unknown query: ${query}`)
 	}
-	const { target, bodyId } = parseQueryArgs(rest, `query ${query}`)
+	const { target, bodyId, featureId } = parseQueryArgs(rest, `query ${query}`)
+	if (query === "refs" && !featureId) {
+		throw new Error("Usage: puppycad query refs [project-id] --target <feature-id>")
+	}
+	if (query !== "refs" && featureId) {
+		throw new Error(`Unknown query ${query} option: --target`)
+	}
 	const { projectId, project } = await loadServerProject(context, target)
+	if (query === "refs" && featureId) {
+		return writeRefsQuery(projectId, project, featureId, context)
+	}
 	if (query !== "features") {
 		const geometry = collectProjectGeometry(project)
 		return writeGeometryQuery(query, projectId, geometry, bodyId, context)
@@ -625,15 +726,15 @@ async function runQueryCommand(args: readonly string[], context: CliContext): Pr
 function writeGeometryQuery(query: string, projectId: string, geometry: CliGeometry, bodyId: string | undefined, context: CliContext): number {
 	const bodies = bodyId ? geometry.bodies.filter((body) => body.id === bodyId) : geometry.bodies
 	if (bodyId && bodies.length === 0) {
-		throw new Error(`Body not found: ${bodyId}`)
+		throw new CliError("semantic", `Body not found: ${bodyId}`)
 	}
 	if (query === "geometry") {
 		if (context.globals.json) {
 			writeStdout(context, JSON.stringify({ projectId, bodies, errors: geometry.errors }, null, 2))
-			return geometry.errors.length > 0 ? 1 : 0
+			return geometry.errors.length > 0 ? CLI_EXIT_CODES.semantic : 0
 		}
 		writeStdout(context, bodies.length > 0 ? bodies.map(formatBodySummary).join("\n") : "No generated geometry.")
-		return geometry.errors.length > 0 ? 1 : 0
+		return geometry.errors.length > 0 ? CLI_EXIT_CODES.semantic : 0
 	}
 	if (query === "bodies") {
 		const summaries = bodies.map((body) => ({
@@ -647,7 +748,7 @@ function writeGeometryQuery(query: string, projectId: string, geometry: CliGeome
 		}))
 		if (context.globals.json) {
 			writeStdout(context, JSON.stringify({ projectId, bodies: summaries, errors: geometry.errors }, null, 2))
-			return geometry.errors.length > 0 ? 1 : 0
+			return geometry.errors.length > 0 ? CLI_EXIT_CODES.semantic : 0
 		}
 		writeStdout(
 			context,
@@ -655,33 +756,59 @@ function writeGeometryQuery(query: string, projectId: string, geometry: CliGeome
 				? summaries.map((body) => `${body.id} part=${body.partId} source=${body.sourceId} vertices=${body.vertices} edges=${body.edges} faces=${body.faces}`).join("\n")
 				: "No bodies."
 		)
-		return geometry.errors.length > 0 ? 1 : 0
+		return geometry.errors.length > 0 ? CLI_EXIT_CODES.semantic : 0
 	}
 	if (query === "faces") {
 		const faces = bodies.flatMap((body) => body.faces.map((face) => ({ ...face, bodyId: body.id, partId: body.partId, sourceId: body.sourceId })))
 		if (context.globals.json) {
 			writeStdout(context, JSON.stringify({ projectId, faces, errors: geometry.errors }, null, 2))
-			return geometry.errors.length > 0 ? 1 : 0
+			return geometry.errors.length > 0 ? CLI_EXIT_CODES.semantic : 0
 		}
 		writeStdout(context, faces.length > 0 ? faces.map((face) => `${face.id} body=${face.bodyId} edges=${face.edgeIds.length}`).join("\n") : "No faces.")
-		return geometry.errors.length > 0 ? 1 : 0
+		return geometry.errors.length > 0 ? CLI_EXIT_CODES.semantic : 0
 	}
 	if (query === "edges") {
 		const edges = bodies.flatMap((body) => body.edges.map((edge) => ({ ...edge, bodyId: body.id, partId: body.partId, sourceId: body.sourceId })))
 		if (context.globals.json) {
 			writeStdout(context, JSON.stringify({ projectId, edges, errors: geometry.errors }, null, 2))
-			return geometry.errors.length > 0 ? 1 : 0
+			return geometry.errors.length > 0 ? CLI_EXIT_CODES.semantic : 0
 		}
 		writeStdout(context, edges.length > 0 ? edges.map((edge) => `${edge.id} body=${edge.bodyId} vertices=${edge.vertexIds.join(",")}`).join("\n") : "No edges.")
-		return geometry.errors.length > 0 ? 1 : 0
+		return geometry.errors.length > 0 ? CLI_EXIT_CODES.semantic : 0
 	}
 	const bboxes = bodies.map((body) => ({ bodyId: body.id, partId: body.partId, sourceId: body.sourceId, bbox: body.bbox }))
 	if (context.globals.json) {
 		writeStdout(context, JSON.stringify({ projectId, bboxes, errors: geometry.errors }, null, 2))
-		return geometry.errors.length > 0 ? 1 : 0
+		return geometry.errors.length > 0 ? CLI_EXIT_CODES.semantic : 0
 	}
 	writeStdout(context, bboxes.length > 0 ? bboxes.map((body) => `${body.bodyId} ${formatBoundingBox(body.bbox)}`).join("\n") : "No bounding boxes.")
-	return geometry.errors.length > 0 ? 1 : 0
+	return geometry.errors.length > 0 ? CLI_EXIT_CODES.semantic : 0
+}
+
+function writeRefsQuery(projectId: string, project: Project, featureId: string, context: CliContext): number {
+	const graph = collectProjectGraph(project)
+	const refs = graph.nodes
+		.filter((node) => node.id === featureId)
+		.map((node) => ({
+			id: node.id,
+			partId: node.partId,
+			dependencies: graph.edges.filter((edge) => edge.partId === node.partId && edge.to === node.id).map((edge) => edge.from),
+			dependents: graph.edges.filter((edge) => edge.partId === node.partId && edge.from === node.id).map((edge) => edge.to)
+		}))
+	if (refs.length === 0) {
+		throw new CliError("semantic", `Feature not found: ${featureId}`)
+	}
+	if (context.globals.json) {
+		writeStdout(context, JSON.stringify({ projectId, refs }, null, 2))
+		return 0
+	}
+	writeStdout(
+		context,
+		refs
+			.map((ref) => `${ref.id} part=${ref.partId} dependencies=${ref.dependencies.join(",") || "none"} dependents=${ref.dependents.join(",") || "none"}`)
+			.join("\n")
+	)
+	return 0
 }
 
 async function runGraphCommand(args: readonly string[], context: CliContext): Promise<number> {
@@ -736,18 +863,25 @@ async function runEvalCommand(args: readonly string[], context: CliContext): Pro
 async function runRenderCommand(args: readonly string[], context: CliContext): Promise<number> {
 	const options = parseRenderArgs(args)
 	const { projectId, project } = await loadProjectForRead(context, options.target)
-	const geometry = collectProjectGeometry(project)
+	const selection = options.only ? selectRenderFeatures(project, options.only) : undefined
+	const geometry = collectProjectGeometry(project, selection)
 	if (geometry.errors.length > 0) {
-		throw new Error(`Cannot render project with geometry errors: ${geometry.errors.map((error) => `${error.partId}/${error.featureId}: ${error.message}`).join("; ")}`)
+		throw new CliError("semantic", `Cannot render project with geometry errors: ${geometry.errors.map((error) => `${error.partId}/${error.featureId}: ${error.message}`).join("; ")}`)
 	}
 	if (geometry.bodies.length === 0) {
-		throw new Error("Project has no generated solid geometry to render.")
+		throw new CliError("semantic", "Project has no generated solid geometry to render.")
 	}
-	const labels = options.showDimensions ? collectProjectDimensionLabels(project) : []
-	const png = await context.renderPng(geometry.bodies, { ...options, labels })
+	const labels = options.showDimensions ? collectProjectDimensionLabels(project, selection) : []
+	const png = await context.renderPng(geometry.bodies, { ...options, labels }).catch((error: unknown) => {
+		throw new CliError("render", formatFileError(error))
+	})
 	const outPath = resolve(context.cwd, options.outPath)
-	await mkdir(dirname(outPath), { recursive: true })
-	await writeFile(outPath, png)
+	try {
+		await mkdir(dirname(outPath), { recursive: true })
+		await writeFile(outPath, png)
+	} catch (error) {
+		throw new CliError("io", `Unable to write render output: ${formatFileError(error)}`)
+	}
 	if (context.globals.json) {
 		writeStdout(
 			context,
@@ -759,6 +893,45 @@ async function runRenderCommand(args: readonly string[], context: CliContext): P
 	return 0
 }
 
+function selectRenderFeatures(project: Project, selectors: readonly string[]): FeatureSelection {
+	const selection = new Map<string, Set<string>>()
+	const matched = new Set<string>()
+	visitProjectNodes(project.items, (node) => {
+		if (isProjectFolder(node) || node.type !== "part") {
+			return
+		}
+		const features = new PCadPart(node.data).getFeatures()
+		const featuresById = new Map(features.map((feature) => [feature.id, feature] as const))
+		const pending: string[] = []
+		for (const feature of features) {
+			const featureSelectors = selectors.filter((candidate) => feature.type === "extrude" && (candidate === feature.id || candidate === getDefaultExtrudeSolidId(feature.id)))
+			for (const selector of featureSelectors) {
+				matched.add(selector)
+			}
+			if (featureSelectors.length > 0) {
+				pending.push(feature.id)
+			}
+		}
+		const included = new Set<string>()
+		while (pending.length > 0) {
+			const feature = featuresById.get(pending.pop() ?? "")
+			if (!feature || included.has(feature.id)) {
+				continue
+			}
+			included.add(feature.id)
+			pending.push(...collectFeatureDependencies(feature))
+		}
+		if (included.size > 0) {
+			selection.set(node.id, included)
+		}
+	})
+	const missing = selectors.filter((selector) => !matched.has(selector))
+	if (missing.length > 0) {
+		throw new CliError("semantic", `Body not found: ${missing.join(", ")}`)
+	}
+	return selection
+}
+
 async function runServerInspect(target: string | undefined, context: CliContext): Promise<number> {
 	const { projectId, project } = await loadServerProject(context, target)
 	const stats = collectProjectStats(project)
@@ -786,16 +959,7 @@ async function runInit(options: InitOptions, cwd: string, output: CliOutput): Pr
 
 async function runFileInspect(options: InspectOptions, cwd: string, output: CliOutput): Promise<number> {
 	const filePath = resolve(cwd, options.target ?? DEFAULT_PROJECT_FILE)
-	const raw = await readFile(filePath, "utf8").catch((error: unknown) => {
-		throw new Error(`Unable to read project file: ${formatFileError(error)}`)
-	})
-	const parsed = JSON.parse(raw) as unknown
-	const project = normalizeProjectFile(parsed)
-	if (!project) {
-		output.stderr(`Invalid PuppyCAD project file: ${filePath}`)
-		return 1
-	}
-
+	const project = await readLocalProjectFile(filePath)
 	const stats = collectProjectStats(project)
 	if (options.json) {
 		output.stdout(JSON.stringify({ file: filePath, project, stats }, null, 2))
@@ -806,6 +970,61 @@ async function runFileInspect(options: InspectOptions, cwd: string, output: CliO
 	return 0
 }
 
+async function runValidateFileJson(target: string | undefined, cwd: string, output: CliOutput): Promise<number> {
+	const filePath = resolve(cwd, target ?? DEFAULT_PROJECT_FILE)
+	try {
+		const project = await readLocalProjectFile(filePath)
+		output.stdout(JSON.stringify({ ok: true, result: { file: filePath, project, stats: collectProjectStats(project) }, diagnostics: [] }, null, 2))
+		return 0
+	} catch (error) {
+		return writeValidateError(error, output)
+	}
+}
+
+function writeValidateError(error: unknown, output: CliOutput): number {
+	const diagnostic = toCliDiagnostic(error)
+	output.stdout(JSON.stringify({ ok: false, diagnostics: [diagnostic] }, null, 2))
+	return CLI_EXIT_CODES[diagnostic.category]
+}
+
+async function runValidateDirectory(dir: string, json: boolean, cwd: string, output: CliOutput): Promise<number> {
+	const dirPath = resolve(cwd, dir)
+	const entries = await readdir(dirPath).catch((error: unknown) => {
+		throw new CliError("io", `Unable to read project directory: ${formatFileError(error)}`)
+	})
+	const results: ValidationResult[] = []
+	for (const name of entries.filter((entry) => entry.endsWith(".pcad")).sort()) {
+		results.push(await validateProjectFile(join(dirPath, name)))
+	}
+	const failed = results.filter((result) => !result.ok).length
+	if (json) {
+		output.stdout(JSON.stringify({ ok: failed === 0, result: { dir: dirPath, results }, diagnostics: [] }, null, 2))
+		return failed > 0 ? 1 : 0
+	}
+	if (results.length === 0) {
+		output.stdout(`No .pcad files in ${dirPath}`)
+		return 0
+	}
+	for (const result of results) {
+		if (result.ok) {
+			output.stdout(`ok ${result.file}`)
+		} else {
+			output.stderr(`fail ${result.file}: ${result.diagnostics.map((diagnostic) => diagnostic.message).join("; ")}`)
+		}
+	}
+	output.stdout(`${results.length} files, ${failed} failed`)
+	return failed > 0 ? 1 : 0
+}
+
+async function validateProjectFile(file: string): Promise<ValidationResult> {
+	try {
+		await readLocalProjectFile(file)
+		return { file, ok: true, diagnostics: [] }
+	} catch (error) {
+		return { file, ok: false, diagnostics: [toCliDiagnostic(error)] }
+	}
+}
+
 function createInitialProject(options: InitOptions): Project {
 	const items: ProjectNode[] = options.empty
 		? []
@@ -895,7 +1114,7 @@ function collectProjectGraph(project: Project): { nodes: CliGraphNode[]; edges:
 	return { nodes, edges }
 }
 
-function collectProjectGeometry(project: Project): CliGeometry {
+function collectProjectGeometry(project: Project, selection?: FeatureSelection): CliGeometry {
 	const bodies: CliGeometryBody[] = []
 	const errors: CliGeometry["errors"] = []
 	visitProjectNodes(project.items, (node) => {
@@ -903,8 +1122,9 @@ function collectProjectGeometry(project: Project): CliGeometry {
 			return
 		}
 		const part = new PCadPart(node.data).getDocument() as PartDocument
+		const included = selection?.get(node.id)
 		for (const feature of part.features) {
-			if (feature.type !== "extrude") {
+			if (feature.type !== "extrude" || (selection && !included?.has(feature.id))) {
 				continue
 			}
 			try {
@@ -918,15 +1138,16 @@ function collectProjectGeometry(project: Project): CliGeometry {
 	return { bodies, errors }
 }
 
-function collectProjectDimensionLabels(project: Project): RenderLabel[] {
+function collectProjectDimensionLabels(project: Project, selection?: FeatureSelection): RenderLabel[] {
 	const labels: RenderLabel[] = []
 	visitProjectNodes(project.items, (node) => {
 		if (isProjectFolder(node) || node.type !== "part") {
 			return
 		}
 		const part = new PCadPart(node.data).getDocument() as PartDocument
+		const included = selection?.get(node.id)
 		for (const feature of part.features) {
-			if (feature.type !== "sketch" || feature.target.type !== "plane") {
+			if (feature.type !== "sketch" || feature.target.type !== "plane" || (selection && !included?.has(feature.id))) {
 				continue
 			}
 			const entitiesById = new Map(feature.entities.map((entity) => [entity.id, entity] as const))
@@ -1080,14 +1301,7 @@ function isProjectFolder(node: ProjectNode): node is Exclude<ProjectNode, Projec
 async function loadProjectForRead(context: CliContext, target: string | undefined): Promise<{ projectId: string; project: Project }> {
 	if (target && (await shouldInspectLocalFile(target, context.cwd))) {
 		const filePath = resolve(context.cwd, target)
-		const raw = await readFile(filePath, "utf8").catch((error: unknown) => {
-			throw new Error(`Unable to read project file: ${formatFileError(error)}`)
-		})
-		const project = normalizeProjectFile(JSON.parse(raw))
-		if (!project) {
-			throw new Error(`Invalid PuppyCAD project file: ${filePath}`)
-		}
-		return { projectId: filePath, project }
+		return { projectId: filePath, project: await readLocalProjectFile(filePath) }
 	}
 	return loadServerProject(context, target)
 }
@@ -1096,13 +1310,7 @@ async function executeCadCommand(context: CliContext, target: string | undefined
 	const syncedCommand: SyncedProjectCommand = { type: "cad", partId, command }
 	if (target && (await shouldInspectLocalFile(target, context.cwd))) {
 		const filePath = resolve(context.cwd, target)
-		const raw = await readFile(filePath, "utf8").catch((error: unknown) => {
-			throw new Error(`Unable to read project file: ${formatFileError(error)}`)
-		})
-		const project = normalizeProjectFile(JSON.parse(raw))
-		if (!project) {
-			throw new Error(`Invalid PuppyCAD project file: ${filePath}`)
-		}
+		const project = await readLocalProjectFile(filePath)
 		const nextProject = applySyncedProjectCommands(project, [syncedCommand])
 		nextProject.revision = project.revision + 1
 		await writeFile(filePath, `${serializeProjectFile(nextProject)}\n`, "utf8")
@@ -1133,6 +1341,23 @@ async function executeCadCommand(context: CliContext, target: string | undefined
 	return 0
 }
 
+async function readLocalProjectFile(filePath: string): Promise<Project> {
+	const raw = await readFile(filePath, "utf8").catch((error: unknown) => {
+		throw new CliError("io", `Unable to read project file: ${formatFileError(error)}`)
+	})
+	let parsed: unknown
+	try {
+		parsed = JSON.parse(raw) as unknown
+	} catch (error) {
+		throw new CliError("parse", `Invalid JSON in project file ${filePath}: ${formatFileError(error)}`)
+	}
+	const project = normalizeProjectFile(parsed)
+	if (!project) {
+		throw new CliError("semantic", `Invalid PuppyCAD project file: ${filePath}`)
+	}
+	return project
+}
+
 async function loadServerProject(context: CliContext, target: string | undefined): Promise<{ projectId: string; project: Project }> {
 	const projectId = await resolveProjectId(context, target)
 	const payload = await parseServerJson<{ projectId?: unknown; project?: unknown }>(context, (await createPuppyCadClient(context)).loadProject(projectId))
@@ -1159,12 +1384,12 @@ async function parseServerJson<T>(context: CliContext, responsePromise: Promise<
 	try {
 		response = await responsePromise
 	} catch (error) {
-		throw new Error(`Unable to reach Puppycad server at ${serverUrl}: ${formatFileError(error)}`)
+		throw new CliError("io", `Unable to reach Puppycad server at ${serverUrl}: ${formatFileError(error)}`)
 	}
-	const payload = (await readJsonPayload(response)) as T & { ok?: unknown; message?: unknown }
+	const payload = (await readJsonPayload(response)) as T & { ok?: unknown; code?: unknown; message?: unknown }
 	if (!response.ok || payload?.ok === false) {
 		const message = typeof payload?.message === "string" ? payload.message : `Server responded with ${response.status}`
-		throw new Error(message)
+		throw response.status === 404 || payload?.code === "not_found" ? new CliError("semantic", message) : new Error(message)
 	}
 	return payload
 }
@@ -1315,6 +1540,7 @@ function parseProjectTargetArgs(args: readonly string[], commandName: string, al
 function parseQueryArgs(args: readonly string[], commandName: string): QueryArgs {
 	let target: string | undefined
 	let bodyId: string | undefined
+	let featureId: string | undefined
 	for (let index = 0; index < args.length; index += 1) {
 		const arg = args[index]
 		if (!arg) {
@@ -1323,6 +1549,15 @@ function parseQueryArgs(args: readonly string[], commandName: string): QueryArgs
 		if (arg === "--help" || arg === "-h") {
 			throw new CliHelpError(formatQueryHelp())
 		}
+		if (arg === "--target") {
+			featureId = readOptionValue(args, index, arg)
+			index += 1
+			continue
+		}
+		if (arg.startsWith("--target=")) {
+			featureId = arg.slice("--target=".length)
+			continue
+		}
 		if (arg === "--body") {
 			bodyId = readOptionValue(args, index, arg)
 			index += 1
@@ -1342,7 +1577,8 @@ function parseQueryArgs(args: readonly string[], commandName: string): QueryArgs
 	}
 	return {
 		...(target ? { target } : {}),
-		...(bodyId ? { bodyId } : {})
+		...(bodyId ? { bodyId } : {}),
+		...(featureId ? { featureId } : {})
 	}
 }
 
@@ -1708,6 +1944,10 @@ function parseRenderArgs(args: readonly string[]): RenderOptions {
 	let outPath: string | undefined
 	let width: number | undefined
 	let height: number | undefined
+	let background: [number, number, number, number] | undefined
+	let projection: RenderProjection | undefined
+	const only: string[] = []
+	let timeoutMs: number | undefined
 	let showDimensions = false
 	for (let index = 0; index < args.length; index += 1) {
 		const arg = args[index]
@@ -1744,6 +1984,42 @@ function parseRenderArgs(args: readonly string[]): RenderOptions {
 			height = parsePositiveInteger(arg.slice("--height=".length), "--height")
 			continue
 		}
+		if (arg === "--background") {
+			background = parseRenderBackground(readOptionValue(args, index, arg))
+			index += 1
+			continue
+		}
+		if (arg.startsWith("--background=")) {
+			background = parseRenderBackground(arg.slice("--background=".length))
+			continue
+		}
+		if (arg === "--projection") {
+			projection = parseRenderProjection(readOptionValue(args, index, arg))
+			index += 1
+			continue
+		}
+		if (arg.startsWith("--projection=")) {
+			projection = parseRenderProjection(arg.slice("--projection=".length))
+			continue
+		}
+		if (arg === "--only") {
+			only.push(...parseRenderSelection(readOptionValue(args, index, arg)))
+			index += 1
+			continue
+		}
+		if (arg.startsWith("--only=")) {
+			only.push(...parseRenderSelection(arg.slice("--only=".length)))
+			continue
+		}
+		if (arg === "--timeout") {
+			timeoutMs = parsePositiveNumber(readOptionValue(args, index, arg), arg) * 1000
+			index += 1
+			continue
+		}
+		if (arg.startsWith("--timeout=")) {
+			timeoutMs = parsePositiveNumber(arg.slice("--timeout=".length), "--timeout") * 1000
+			continue
+		}
 		if (arg === "--show-dimensions") {
 			showDimensions = true
 			continue
@@ -1764,10 +2040,44 @@ function parseRenderArgs(args: readonly string[]): RenderOptions {
 		...(target ? { target } : {}),
 		...(width ? { width } : {}),
 		...(height ? { height } : {}),
+		...(background ? { background } : {}),
+		...(projection ? { projection } : {}),
+		...(only.length > 0 ? { only } : {}),
+		...(timeoutMs ? { timeoutMs } : {}),
 		...(showDimensions ? { showDimensions } : {})
 	}
 }
 
+function parseRenderSelection(value: string): string[] {
+	const selectors = value
+		.split(",")
+		.map((selector) => selector.trim())
+		.filter(Boolean)
+	if (selectors.length === 0) {
+		throw new Error("--only requires at least one body or feature id.")
+	}
+	return selectors
+}
+
+function parseRenderProjection(value: string): RenderProjection {
+	if (value === "perspective" || value === "orthographic") {
+		return value
+	}
+	throw new Error(`Invalid render projection: ${value}`)
+}
+
+function parseRenderBackground(value: string): [number, number, number, number] {
+	if (value === "transparent") {
+		return [0, 0, 0, 0]
+	}
+	const match = value.match(/^#([0-9a-f]{6})([0-9a-f]{2})?$/i)
+	if (!match?.[1]) {
+		throw new Error("--background requires transparent, #rrggbb, or #rrggbbaa.")
+	}
+	const channels = `${match[1]}${match[2] ?? "ff"}`
+	return [0, 2, 4, 6].map((offset) => Number.parseInt(channels.slice(offset, offset + 2), 16) / 255) as [number, number, number, number]
+}
+
 function parsePositiveInteger(value: string, option: string): number {
 	const parsed = Number(value)
 	if (!Number.isInteger(parsed) || parsed <= 0) {
@@ -1864,11 +2174,13 @@ function formatHelp(): string {
 		"  query features [project-id]     List part features",
 		"  query geometry [project-id]     List generated bodies/faces/edges",
 		"  query bodies|faces|edges|bbox   Inspect generated geometry",
+		"  query refs --target <id>        List a feature's dependencies and dependents",
 		"  graph [project-id]              Print the feature graph",
 		"  eval [project-id]               Validate/evaluate the project snapshot",
 		"  render [project-id] --out <png> Render a PNG preview",
 		"  init [file]                     Create a local PuppyCAD project file",
 		"  validate [file]                 Validate and summarize a local project file",
+		"  validate --dir <path>           Validate every .pcad file in a directory",
 		"",
 		"Global options:",
 		"  --server-url <url>              Server URL",
@@ -1877,7 +2189,11 @@ function formatHelp(): string {
 		"  --quiet                         Suppress successful human output",
 		"  --verbose                       Enable verbose output where supported",
 		"  -h, --help                      Show help",
-		"  -v, --version                   Show version"
+		"  -v, --version                   Show version",
+		"",
+		"Exit codes:",
+		"  1 failure, 2 io, 3 parse, 4 semantic (invalid project or missing target), 5 render",
+		"  With --json, errors are printed to stderr as { ok: false, diagnostics }"
 	].join("\n")
 }
 
@@ -1918,7 +2234,8 @@ function formatQueryHelp(): string {
 		"  bodies [project-id] --json",
 		"  faces [project-id] [--body <body-id>] --json",
 		"  edges [project-id] [--body <body-id>] --json",
-		"  bbox [project-id] [--body <body-id>] --json"
+		"  bbox [project-id] [--body <body-id>] --json",
+		"  refs [project-id] --target <feature-id> --json"
 	].join("\n")
 }
 
@@ -1930,6 +2247,10 @@ function formatRenderHelp(): string {
 		"  -o, --out <file>   Write PNG preview to file",
 		"  --width <px>       Image width, default 1024",
 		"  --height <px>      Image height, default 768",
+		"  --background <c>   transparent, #rrggbb, or #rrggbbaa",
+		"  --projection <p>   perspective or orthographic, default perspective",
+		"  --only <ids>       Build and render only these bodies plus their dependencies",
+		"  --timeout <s>      Fail if the headless renderer takes longer than this",
 		"  --show-dimensions  Draw sketch dimension labels"
 	].join("\n")
 }
@@ -1953,6 +2274,16 @@ function formatInspectHelp(): string {
 	return ["Usage: puppycad inspect [project-id|file] [options]", "", "Options:", "  --json    Print project data and stats as JSON"].join("\n")
 }
 
+function formatValidateHelp(): string {
+	return [
+		"Usage: puppycad validate [file] [options]",
+		"",
+		"Options:",
+		"  --dir <path>   Validate every .pcad file in a directory",
+		"  --json         Print { ok, result?, diagnostics } to stdout, including errors"
+	].join("\n")
+}
+
 function readOptionValue(args: readonly string[], index: number, option: string): string {
 	const value = args[index + 1]
 	if (!value || value.startsWith("-")) {
@@ -1978,6 +2309,13 @@ function isNotFoundError(error: unknown): boolean {
 	return !!error && typeof error === "object" && "code" in error && (error as { code?: unknown }).code === "ENOENT"
 }
 
+function toCliDiagnostic(error: unknown): CliDiagnostic {
+	if (error instanceof CliError) {
+		return { category: error.category, message: error.message }
+	}
+	return { category: "failure", message: error instanceof Error ? error.message : String(error) }
+}
+
 class CliHelpError extends Error {
 	public constructor(message: string) {
 		super(message)
@@ -1985,6 +2323,16 @@ class CliHelpError extends Error {
 	}
 }
 
+class CliError extends Error {
+	public readonly category: CliErrorCategory
+
+	public constructor(category: CliErrorCategory, message: string) {
+		super(message)
+		this.name = "CliError"
+		this.category = category
+	}
+}
+
 async function main(): Promise<void> {
 	const code = await runPuppycadCli(Bun.argv.slice(2), { version: await readPackageVersion() })
 	process.exitCode = code
@@ -1,8 +1,18 @@
 import index from "./ui/index.html"
 import { postMcp } from "./mcp"
+import { withRequestLog, type RequestLogger } from "./server/request-log"
 import { getHealth, getProject, getProjectEvents, getProjects, postProject, postProjectCommands, postProjectRedo, postProjectUndo, putProject } from "./server/save-project"
+import { getTemplate, getTemplates } from "./server/templates"
 
-console.log("Starting server on http://localhost:5337")
+const serverUrl = "http://localhost:5337"
+const quiet = process.argv.includes("--quiet")
+const logJson = !quiet && process.argv.includes("--log-json")
+
+if (logJson) {
+	console.log(JSON.stringify({ message: "Starting server", url: serverUrl }))
+} else if (!quiet) {
+	console.log(`Starting server on ${serverUrl}`)
+}
 
 const pngHeaders = {
 	"Content-Type": "image/png"
@@ -12,6 +22,12 @@ const manifestHeaders = {
 	"Content-Type": "application/manifest+json"
 }
 
+const requestLogger: RequestLogger | null = logJson ? (line) => console.log(line) : null
+
+function logged<T extends Request>(handler: (request: T) => Response | Promise<Response>): (request: T) => Response | Promise<Response> {
+	return requestLogger ? withRequestLog(handler, requestLogger) : handler
+}
+
 Bun.serve({
 	port: 5337,
 	routes: {
@@ -28,30 +44,36 @@ Bun.serve({
 			GET: () => new Response(Bun.file(new URL("./ui/manifest.webmanifest", import.meta.url)), { headers: manifestHeaders })
 		},
 		"/mcp": {
-			POST: postMcp
+			POST: logged(postMcp)
 		},
 		"/health": {
-			GET: getHealth
+			GET: logged(getHealth)
 		},
 		"/api/projects": {
-			GET: getProjects,
-			POST: postProject
+			GET: logged(getProjects),
+			POST: logged(postProject)
 		},
 		"/api/projects/:projectId": {
-			GET: (request) => getProject(request, request.params.projectId),
-			PUT: (request) => putProject(request, request.params.projectId)
+			GET: logged((request) => getProject(request, request.params.projectId)),
+			PUT: logged((request) => putProject(request, request.params.projectId))
 		},
 		"/api/projects/:projectId/commands": {
-			POST: (request) => postProjectCommands(request, request.params.projectId)
+			POST: logged((request) => postProjectCommands(request, request.params.projectId))
 		},
 		"/api/projects/:projectId/undo": {
-			POST: (request) => postProjectUndo(request, request.params.projectId)
+			POST: logged((request) => postProjectUndo(request, request.params.projectId))
 		},
 		"/api/projects/:projectId/redo": {
-			POST: (request) => postProjectRedo(request, request.params.projectId)
+			POST: logged((request) => postProjectRedo(request, request.params.projectId))
 		},
 		"/api/projects/:projectId/events": {
-			GET: (request) => getProjectEvents(request, request.params.projectId)
+			GET: logged((request) => getProjectEvents(request, request.params.projectId))
+		},
+		"/api/templates": {
+			GET: logged(getTemplates)
+		},
+		"/api/templates/:name": {
+			GET: logged((request) => getTemplate(request, request.params.name))
 		},
 		"/*": index
 	}
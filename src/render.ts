@@ -19,10 +19,14 @@ export type RenderLabel = {
 	readonly position: Vector3D
 }
 
+export type RenderProjection = "perspective" | "orthographic"
+
 export type RenderPreviewOptions = {
 	width?: number
 	height?: number
 	background?: [number, number, number, number]
+	projection?: RenderProjection
+	timeoutMs?: number
 	labels?: readonly RenderLabel[]
 	createContext?: HeadlessGlFactory
 }
@@ -50,12 +54,13 @@ export async function renderProjectPreviewPng(bodies: readonly RenderGeometryBod
 	const width = normalizeImageDimension(options.width, DEFAULT_WIDTH, "width")
 	const height = normalizeImageDimension(options.height, DEFAULT_HEIGHT, "height")
 	const background = options.background ?? DEFAULT_BACKGROUND
+	const projection = options.projection ?? "perspective"
 	const mesh = buildPreviewMesh(bodies)
 	if (mesh.positions.length === 0) {
 		throw new Error("Project has no generated solid geometry to render.")
 	}
 	if (!options.createContext) {
-		return renderMeshWithNodeWorker(mesh, width, height, background, options.labels ?? [])
+		return renderMeshWithNodeWorker(mesh, width, height, background, projection, options.labels ?? [], options.timeoutMs)
 	}
 	const createContext = options.createContext
 	const gl = createContext(width, height, { preserveDrawingBuffer: true, antialias: true })
@@ -73,7 +78,7 @@ export async function renderProjectPreviewPng(bodies: readonly RenderGeometryBod
 	gl.cullFace(gl.BACK)
 	gl.clear(gl.COLOR_BUFFER_BIT | gl.DEPTH_BUFFER_BIT)
 
-	const modelViewProjection = createPreviewMatrix(mesh.bounds, width / height)
+	const modelViewProjection = createPreviewMatrix(mesh.bounds, width / height, projection)
 	const normalMatrix = createNormalMatrix(mesh.modelMatrix)
 
 	bindAttribute(gl, program, "position", mesh.positions, 3)
@@ -206,12 +211,15 @@ async function renderMeshWithNodeWorker(
 	width: number,
 	height: number,
 	background: [number, number, number, number],
-	labels: readonly RenderLabel[]
+	projection: RenderProjection,
+	labels: readonly RenderLabel[],
+	timeoutMs?: number
 ): Promise<Uint8Array> {
-	const input = JSON.stringify({ width, height, background, labels, mesh, vertexShader: VERTEX_SHADER_SOURCE, fragmentShader: FRAGMENT_SHADER_SOURCE })
+	const input = JSON.stringify({ width, height, background, projection, labels, mesh, vertexShader: VERTEX_SHADER_SOURCE, fragmentShader: FRAGMENT_SHADER_SOURCE })
 	const child = spawn(process.env.PUPPYCAD_NODE_RENDERER ?? "node", ["-e", NODE_RENDER_WORKER], {
 		stdio: ["pipe", "pipe", "pipe"],
-		env: process.env
+		env: process.env,
+		detached: true
 	})
 	const stdout: Buffer[] = []
 	const stderr: Buffer[] = []
@@ -219,8 +227,24 @@ async function renderMeshWithNodeWorker(
 	child.stderr.on("data", (chunk: Buffer) => stderr.push(chunk))
 	child.stdin.end(input)
 	const code = await new Promise<number | null>((resolve, reject) => {
-		child.on("error", reject)
-		child.on("close", resolve)
+		const timer =
+			timeoutMs === undefined
+				? undefined
+				: setTimeout(() => {
+						killWorkerProcessGroup(child.pid)
+						child.stdout.destroy()
+						child.stderr.destroy()
+						child.stdin.destroy()
+						reject(new Error(`Headless rendering timed out after ${timeoutMs}ms.`))
+					}, timeoutMs)
+		child.on("error", (error) => {
+			clearTimeout(timer)
+			reject(error)
+		})
+		child.on("close", (exitCode) => {
+			clearTimeout(timer)
+			resolve(exitCode)
+		})
 	})
 	if (code !== 0) {
 		throw new Error(`Headless rendering failed in node worker. ${Buffer.concat(stderr).toString("utf8").trim()}`.trim())
@@ -235,6 +259,18 @@ async function renderMeshWithNodeWorker(
 	return Buffer.from(payload.pngBase64, "base64")
 }
 
+// The worker runs in its own process group so a timeout also kills anything it spawned, such as a shell wrapper's children.
+function killWorkerProcessGroup(pid: number | undefined): void {
+	if (pid === undefined) {
+		return
+	}
+	try {
+		process.kill(-pid, "SIGKILL")
+	} catch {
+		// The group has already exited.
+	}
+}
+
 const NODE_RENDER_WORKER = String.raw`
 const { createRequire } = require('node:module')
 const zlib = require('node:zlib')
@@ -258,7 +294,7 @@ process.stdin.on('end', () => {
     gl.enable(gl.CULL_FACE)
     gl.cullFace(gl.BACK)
     gl.clear(gl.COLOR_BUFFER_BIT | gl.DEPTH_BUFFER_BIT)
-    const mvp = createPreviewMatrix(input.mesh.bounds, input.width / input.height)
+    const mvp = createPreviewMatrix(input.mesh.bounds, input.width / input.height, input.projection)
     bindAttribute(gl, program, 'position', input.mesh.positions, 3)
     bindAttribute(gl, program, 'normal', input.mesh.normals, 3)
     gl.uniformMatrix4fv(gl.getUniformLocation(program, 'modelViewProjection'), false, new Float32Array(mvp))
@@ -297,17 +333,26 @@ function createShader(gl, type, source) {
   if (!gl.getShaderParameter(shader, gl.COMPILE_STATUS)) throw new Error(gl.getShaderInfoLog(shader) || 'Unable to compile WebGL shader.')
   return shader
 }
-function createPreviewMatrix(bounds, aspect) {
+function createPreviewMatrix(bounds, aspect, projection) {
   const center = [(bounds.min[0] + bounds.max[0]) / 2, (bounds.min[1] + bounds.max[1]) / 2, (bounds.min[2] + bounds.max[2]) / 2]
   const size = Math.max(bounds.max[0] - bounds.min[0], bounds.max[1] - bounds.min[1], bounds.max[2] - bounds.min[2], 1)
-  const eye = [center[0] + size * 1.7, center[1] + size * 1.25, center[2] + size * 1.9]
-  return multiply4(perspective(35 * Math.PI / 180, aspect, 0.1, size * 8), lookAt(eye, center, [0, 1, 0]))
+  const offset = [size * 1.7, size * 1.25, size * 1.9]
+  const eye = [center[0] + offset[0], center[1] + offset[1], center[2] + offset[2]]
+  const fovy = 35 * Math.PI / 180
+  const lens = projection === 'orthographic'
+    ? orthographic(Math.tan(fovy / 2) * Math.hypot(offset[0], offset[1], offset[2]), aspect, 0.1, size * 8)
+    : perspective(fovy, aspect, 0.1, size * 8)
+  return multiply4(lens, lookAt(eye, center, [0, 1, 0]))
 }
 function perspective(fovy, aspect, near, far) {
   const f = 1 / Math.tan(fovy / 2)
   const nf = 1 / (near - far)
   return [f / aspect, 0, 0, 0, 0, f, 0, 0, 0, 0, (far + near) * nf, -1, 0, 0, 2 * far * near * nf, 0]
 }
+function orthographic(halfHeight, aspect, near, far) {
+  const nf = 1 / (near - far)
+  return [1 / (halfHeight * aspect), 0, 0, 0, 0, 1 / halfHeight, 0, 0, 0, 0, 2 * nf, 0, 0, 0, (far + near) * nf, 1]
+}
 function lookAt(eye, target, up) {
   const z = normalize3(sub3(eye, target))
   const x = normalize3(cross3(up, z))
@@ -495,13 +540,19 @@ function createShader(gl: WebGLRenderingContext, type: number, source: string):
 	return shader
 }
 
-function createPreviewMatrix(bounds: Bounds, aspect: number): Mat4 {
+function createPreviewMatrix(bounds: Bounds, aspect: number, projection: RenderProjection): Mat4 {
 	const center = boundsCenter(bounds)
 	const size = Math.max(bounds.max[0] - bounds.min[0], bounds.max[1] - bounds.min[1], bounds.max[2] - bounds.min[2], 1)
-	const eye = add3(center, [size * 1.7, size * 1.25, size * 1.9])
+	const offset: Vec3 = [size * 1.7, size * 1.25, size * 1.9]
+	const eye = add3(center, offset)
 	const view = lookAt(eye, center, [0, 1, 0])
-	const projection = perspective((35 * Math.PI) / 180, aspect, 0.1, size * 8)
-	return multiply4(projection, view)
+	const fovy = (35 * Math.PI) / 180
+	// Orthographic previews keep the same framing as the perspective view at the target distance.
+	const lens =
+		projection === "orthographic"
+			? orthographic(Math.tan(fovy / 2) * Math.hypot(offset[0], offset[1], offset[2]), aspect, 0.1, size * 8)
+			: perspective(fovy, aspect, 0.1, size * 8)
+	return multiply4(lens, view)
 }
 
 function createNormalMatrix(model: Mat4): Mat4 {
@@ -592,6 +643,11 @@ function perspective(fovy: number, aspect: number, near: number, far: number): M
 	return [f / aspect, 0, 0, 0, 0, f, 0, 0, 0, 0, (far + near) * nf, -1, 0, 0, 2 * far * near * nf, 0]
 }
 
+function orthographic(halfHeight: number, aspect: number, near: number, far: number): Mat4 {
+	const nf = 1 / (near - far)
+	return [1 / (halfHeight * aspect), 0, 0, 0, 0, 1 / halfHeight, 0, 0, 0, 0, 2 * nf, 0, 0, 0, (far + near) * nf, 1]
+}
+
 function lookAt(eye: Vec3, target: Vec3, up: Vec3): Mat4 {
 	const z = normalize3(sub3(eye, target))
 	const x = normalize3(cross3(up, z))
@@ -0,0 +1,40 @@
+export type RequestLogEntry = {
+	method: string
+	path: string
+	projectId?: string
+	status: number
+	// Time until the handler returned its Response. Streaming bodies, such as project events, keep sending after this.
+	durationMs: number
+	requestId?: string
+}
+
+export type RequestLogger = (line: string) => void
+
+type RouteHandler<T extends Request> = (request: T) => Response | Promise<Response>
+
+export function withRequestLog<T extends Request>(handler: RouteHandler<T>, log: RequestLogger): (request: T) => Promise<Response> {
+	return async (request) => {
+		const startedAt = performance.now()
+		try {
+			const response = await handler(request)
+			log(JSON.stringify(createRequestLogEntry(request, response.status, performance.now() - startedAt)))
+			return response
+		} catch (error) {
+			log(JSON.stringify(createRequestLogEntry(request, 500, performance.now() - startedAt)))
+			throw error
+		}
+	}
+}
+
+function createRequestLogEntry(request: Request, status: number, durationMs: number): RequestLogEntry {
+	const projectId = (request as Request & { params?: Record<string, string | undefined> }).params?.projectId
+	const requestId = request.headers.get("x-request-id")
+	return {
+		method: request.method,
+		path: new URL(request.url).pathname,
+		...(projectId ? { projectId } : {}),
+		status,
+		durationMs: Math.round(durationMs * 1000) / 1000,
+		...(requestId ? { requestId } : {})
+	}
+}
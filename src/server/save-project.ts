@@ -1,3 +1,4 @@
+import { createHash } from "node:crypto"
 import { existsSync } from "node:fs"
 import { mkdir, readFile, readdir } from "node:fs/promises"
 import type { Project } from "../contract"
@@ -99,19 +100,33 @@ export async function persistProject(projectId: string, project: Project): Promi
 }
 
 export async function getProject(request: Request, projectId: string): Promise<Response> {
-	void request
 	try {
 		const project = await loadProject(projectId)
 		if (!project) {
 			return Response.json({ ok: false, code: "not_found", message: "Project not found." }, { status: 404 })
 		}
-		return Response.json({ ok: true, projectId, revision: project.revision, project, ...getProjectHistoryState(projectId) })
+		const body = JSON.stringify({ ok: true, projectId, revision: project.revision, project, ...getProjectHistoryState(projectId) })
+		const etag = `"${createHash("sha256").update(body).digest("hex")}"`
+		if (matchesEtag(request.headers.get("if-none-match"), etag)) {
+			return new Response(null, { status: 304, headers: { ETag: etag } })
+		}
+		return new Response(body, { headers: { "Content-Type": "application/json;charset=utf-8", ETag: etag } })
 	} catch (error) {
 		console.error("Failed to load project", error)
 		return Response.json({ ok: false, code: "load_failed", message: "Unable to load project." }, { status: 500 })
 	}
 }
 
+function matchesEtag(ifNoneMatch: string | null, etag: string): boolean {
+	if (!ifNoneMatch) {
+		return false
+	}
+	return ifNoneMatch.split(",").some((candidate) => {
+		const value = candidate.trim()
+		return value === "*" || value.replace(/^W\//, "") === etag
+	})
+}
+
 export async function putProject(request: Request, projectId: string): Promise<Response> {
 	let payload: unknown
 	try {
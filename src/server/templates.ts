@@ -0,0 +1,81 @@
+import type { Project } from "../contract"
+import { PCadPart } from "../pcad/project"
+import { createProjectFile, serializeProjectFile } from "../project-file"
+import type { PartFeature, SketchPlane } from "../schema"
+
+type ProjectTemplate = {
+	name: string
+	description: string
+	partName: string
+	features: PartFeature[]
+}
+
+const PROJECT_TEMPLATES: readonly ProjectTemplate[] = [
+	{
+		name: "box",
+		description: "20 mm cube extruded from an XY sketch",
+		partName: "Box",
+		features: createRectangleExtrude(1, "XY", 20, 20, 20)
+	},
+	{
+		name: "plate",
+		description: "60 x 40 mm plate, 3 mm thick",
+		partName: "Plate",
+		features: createRectangleExtrude(1, "XY", 60, 40, 3)
+	},
+	{
+		name: "bracket",
+		description: "L bracket from an XY base plate and an XZ upright",
+		partName: "Bracket",
+		features: [...createRectangleExtrude(1, "XY", 40, 20, 4), ...createRectangleExtrude(2, "XZ", 40, 30, 4)]
+	}
+]
+
+export async function getTemplates(_request: Request): Promise<Response> {
+	return Response.json({ ok: true, templates: PROJECT_TEMPLATES.map(({ name, description }) => ({ name, description })) })
+}
+
+export async function getTemplate(_request: Request, name: string): Promise<Response> {
+	const template = PROJECT_TEMPLATES.find((entry) => entry.name === name)
+	if (!template) {
+		return Response.json({ ok: false, code: "not_found", message: "Template not found." }, { status: 404 })
+	}
+	return new Response(serializeProjectFile(createTemplateProject(template)), { headers: { "Content-Type": "application/json;charset=utf-8" } })
+}
+
+function createTemplateProject(template: ProjectTemplate): Project {
+	return createProjectFile({
+		items: [{ id: "part-1", type: "part", name: template.partName, data: new PCadPart({ features: template.features }).getDocument() }],
+		selectedPath: [0]
+	})
+}
+
+function createRectangleExtrude(index: number, plane: SketchPlane, width: number, height: number, depth: number): PartFeature[] {
+	const sketchId = `sketch-${index}`
+	const entityId = `rect-${index}`
+	const profileId = `${sketchId}-profile-1`
+	return [
+		{
+			type: "sketch",
+			id: sketchId,
+			name: `Sketch ${index}`,
+			dirty: false,
+			target: { type: "plane", plane },
+			entities: [{ id: entityId, type: "cornerRectangle", p0: { x: 0, y: 0 }, p1: { x: width, y: height } }],
+			dimensions: [
+				{ id: `${sketchId}-rectangleWidth-${entityId}`, type: "rectangleWidth", entityId, value: width },
+				{ id: `${sketchId}-rectangleHeight-${entityId}`, type: "rectangleHeight", entityId, value: height }
+			],
+			vertices: [],
+			loops: [],
+			profiles: [{ id: profileId, outerLoopId: "loop-1", holeLoopIds: [] }]
+		},
+		{
+			type: "extrude",
+			id: `extrude-${index}`,
+			name: `Extrude ${index}`,
+			target: { type: "profileRef", sketchId, profileId },
+			depth
+		}
+	]
+}
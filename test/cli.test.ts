@@ -223,10 +223,174 @@ describe("puppycad CLI", () => {
 		const { output, stderr } = createOutput()
 		const code = await runPuppycadCli(["inspect", "bad.pcad"], { cwd, output })
 
-		expect(code).toBe(1)
+		expect(code).toBe(4)
 		expect(stderr.join("\n")).toContain("Invalid PuppyCAD project file")
 	})
 
+	it("uses distinct exit codes and a JSON error envelope under --json", async () => {
+		const cwd = await createTempDir()
+		await writeFile(join(cwd, "broken.pcad"), '{"version":', "utf8")
+		await writeFile(join(cwd, "bad.pcad"), '{"version":99,"items":[]}', "utf8")
+
+		const syntaxOutput = createOutput()
+		const syntaxCode = await runPuppycadCli(["--json", "inspect", "broken.pcad"], { cwd, output: syntaxOutput.output })
+		expect(syntaxCode).toBe(3)
+		expect(syntaxOutput.stdout).toEqual([])
+		const syntaxError = JSON.parse(syntaxOutput.stderr.join("\n")) as { ok: boolean; diagnostics: { category: string; message: string }[] }
+		expect(syntaxError.ok).toBe(false)
+		expect(syntaxError.diagnostics).toHaveLength(1)
+		expect(syntaxError.diagnostics[0]?.category).toBe("parse")
+		expect(syntaxError.diagnostics[0]?.message).toContain(`Invalid JSON in project file ${join(cwd, "broken.pcad")}`)
+
+		const semanticOutput = createOutput()
+		const semanticCode = await runPuppycadCli(["--json", "inspect", "bad.pcad"], { cwd, output: semanticOutput.output })
+		expect(semanticCode).toBe(4)
+		expect(JSON.parse(semanticOutput.stderr.join("\n"))).toEqual({ ok: false, diagnostics: [{ category: "semantic", message: `Invalid PuppyCAD project file: ${join(cwd, "bad.pcad")}` }] })
+
+		const initOutput = createOutput()
+		await runPuppycadCli(["init", "good.pcad"], { cwd, output: initOutput.output })
+		const missingTargetOutput = createOutput()
+		const missingTargetCode = await runPuppycadCli(["--json", "render", "good.pcad", "--out", "missing.png", "--only", "extrude-9"], { cwd, output: missingTargetOutput.output })
+		expect(missingTargetCode).toBe(4)
+		expect(JSON.parse(missingTargetOutput.stderr.join("\n"))).toEqual({ ok: false, diagnostics: [{ category: "semantic", message: "Body not found: extrude-9" }] })
+
+		const ioOutput = createOutput()
+		const ioCode = await runPuppycadCli(["inspect", "missing.pcad"], { cwd, output: ioOutput.output })
+		expect(ioCode).toBe(2)
+		expect(ioOutput.stderr.join("\n")).toContain("Unable to read project file")
+
+		await writeFile(join(cwd, "cube.pcad"), JSON.stringify(createProject(new PCadPart(createPartDocument()).getDocument())), "utf8")
+		const renderOutput = createOutput()
+		const renderCode = await runPuppycadCli(["--json", "render", "cube.pcad", "--out", "cube.png"], {
+			cwd,
+			output: renderOutput.output,
+			renderPng: async () => {
+				throw new Error("Headless rendering failed in node worker.")
+			}
+		})
+		expect(renderCode).toBe(5)
+		expect(JSON.parse(renderOutput.stderr.join("\n"))).toEqual({ ok: false, diagnostics: [{ category: "render", message: "Headless rendering failed in node worker." }] })
+
+		const writeOutput = createOutput()
+		const writeCode = await runPuppycadCli(["--json", "render", "cube.pcad", "--out", "cube.pcad/cube.png"], {
+			cwd,
+			output: writeOutput.output,
+			renderPng: async () => new Uint8Array([137, 80, 78, 71, 13, 10, 26, 10])
+		})
+		expect(writeCode).toBe(2)
+		const writeError = JSON.parse(writeOutput.stderr.join("\n")) as { ok: boolean; diagnostics: { category: string; message: string }[] }
+		expect(writeError.diagnostics[0]?.category).toBe("io")
+		expect(writeError.diagnostics[0]?.message).toContain("Unable to write render output")
+
+		const missingProjectOutput = createOutput()
+		const missingProjectCode = await runPuppycadCli(["--server-url", "http://server.test", "--json", "render", `cli-missing-project-${crypto.randomUUID()}`, "--out", "missing.png"], {
+			cwd,
+			output: missingProjectOutput.output,
+			fetch: createServerFetch()
+		})
+		expect(missingProjectCode).toBe(4)
+		expect(JSON.parse(missingProjectOutput.stderr.join("\n"))).toEqual({ ok: false, diagnostics: [{ category: "semantic", message: "Project not found." }] })
+	})
+
+	it("writes validate results and errors to stdout under --json", async () => {
+		const cwd = await createTempDir()
+		const initOutput = createOutput()
+		await runPuppycadCli(["init", "good.pcad"], { cwd, output: initOutput.output })
+		await writeFile(join(cwd, "bad.pcad"), '{"version":99,"items":[]}', "utf8")
+		await writeFile(join(cwd, "broken.pcad"), "{", "utf8")
+
+		const badOutput = createOutput()
+		const badCode = await runPuppycadCli(["validate", "bad.pcad", "--json"], { cwd, output: badOutput.output })
+		expect(badCode).toBe(4)
+		expect(badOutput.stderr).toEqual([])
+		expect(JSON.parse(badOutput.stdout.join("\n"))).toEqual({ ok: false, diagnostics: [{ category: "semantic", message: `Invalid PuppyCAD project file: ${join(cwd, "bad.pcad")}` }] })
+
+		const brokenOutput = createOutput()
+		const brokenCode = await runPuppycadCli(["--json", "validate", "broken.pcad"], { cwd, output: brokenOutput.output })
+		expect(brokenCode).toBe(3)
+		expect(brokenOutput.stderr).toEqual([])
+		const broken = JSON.parse(brokenOutput.stdout.join("\n")) as { ok: boolean; result?: unknown; diagnostics: { category: string }[] }
+		expect(broken.ok).toBe(false)
+		expect(broken.result).toBeUndefined()
+		expect(broken.diagnostics.map((diagnostic) => diagnostic.category)).toEqual(["parse"])
+
+		const goodOutput = createOutput()
+		const goodCode = await runPuppycadCli(["--json", "validate", "good.pcad"], { cwd, output: goodOutput.output })
+		expect(goodCode).toBe(0)
+		expect(goodOutput.stderr).toEqual([])
+		const good = JSON.parse(goodOutput.stdout.join("\n")) as { ok: boolean; result?: { file: string; stats: { documents: number } }; diagnostics: unknown[] }
+		expect(good.ok).toBe(true)
+		expect(good.result?.file).toBe(join(cwd, "good.pcad"))
+		expect(good.result?.stats.documents).toBe(1)
+		expect(good.diagnostics).toEqual([])
+
+		const missingDirOutput = createOutput()
+		const missingDirCode = await runPuppycadCli(["--json", "validate", "--dir", "missing-dir"], { cwd, output: missingDirOutput.output })
+		expect(missingDirCode).toBe(2)
+		expect(missingDirOutput.stderr).toEqual([])
+		const missingDir = JSON.parse(missingDirOutput.stdout.join("\n")) as { ok: boolean; diagnostics: { category: string; message: string }[] }
+		expect(missingDir.ok).toBe(false)
+		expect(missingDir.diagnostics[0]?.category).toBe("io")
+		expect(missingDir.diagnostics[0]?.message).toContain("Unable to read project directory")
+
+		const usageOutput = createOutput()
+		const usageCode = await runPuppycadCli(["--json", "validate", "--bogus"], { cwd, output: usageOutput.output })
+		expect(usageCode).toBe(1)
+		expect(usageOutput.stderr).toEqual([])
+		expect(JSON.parse(usageOutput.stdout.join("\n"))).toEqual({ ok: false, diagnostics: [{ category: "failure", message: "Unknown validate option: --bogus" }] })
+
+		const humanOutput = createOutput()
+		const humanCode = await runPuppycadCli(["validate", "bad.pcad"], { cwd, output: humanOutput.output })
+		expect(humanCode).toBe(4)
+		expect(humanOutput.stdout).toEqual([])
+		expect(humanOutput.stderr).toEqual([`Invalid PuppyCAD project file: ${join(cwd, "bad.pcad")}`])
+	})
+
+	it("validates every project file in a directory", async () => {
+		const cwd = await createTempDir()
+		const initOutput = createOutput()
+		await runPuppycadCli(["init", "projects/good.pcad"], { cwd, output: initOutput.output })
+		await writeFile(join(cwd, "projects", "bad.pcad"), '{"version":99,"items":[]}', "utf8")
+		await writeFile(join(cwd, "projects", "broken.pcad"), "{", "utf8")
+		await writeFile(join(cwd, "projects", "notes.txt"), "not a project", "utf8")
+		const badFile = join(cwd, "projects", "bad.pcad")
+		const brokenFile = join(cwd, "projects", "broken.pcad")
+		const goodFile = join(cwd, "projects", "good.pcad")
+
+		const jsonOutput = createOutput()
+		const jsonCode = await runPuppycadCli(["--json", "validate", "--dir", "projects"], { cwd, output: jsonOutput.output })
+		expect(jsonCode).toBe(1)
+		expect(jsonOutput.stderr).toEqual([])
+		const report = JSON.parse(jsonOutput.stdout.join("\n")) as {
+			ok: boolean
+			result: { dir: string; results: { file: string; ok: boolean; diagnostics: { category: string; message: string }[] }[] }
+			diagnostics: unknown[]
+		}
+		expect(report.ok).toBe(false)
+		expect(report.diagnostics).toEqual([])
+		expect(report.result.dir).toBe(join(cwd, "projects"))
+		expect(report.result.results.map((result) => [result.file, result.ok, result.diagnostics.map((diagnostic) => diagnostic.category)])).toEqual([
+			[badFile, false, ["semantic"]],
+			[brokenFile, false, ["parse"]],
+			[goodFile, true, []]
+		])
+		expect(report.result.results[0]?.diagnostics[0]?.message).toBe(`Invalid PuppyCAD project file: ${badFile}`)
+		expect(report.result.results[1]?.diagnostics[0]?.message).toContain(`Invalid JSON in project file ${brokenFile}`)
+
+		const { output, stdout, stderr } = createOutput()
+		const code = await runPuppycadCli(["validate", "--dir=projects"], { cwd, output })
+		expect(code).toBe(1)
+		expect(stdout).toEqual([`ok ${goodFile}`, "3 files, 2 failed"])
+		expect(stderr).toHaveLength(2)
+		expect(stderr[0]).toBe(`fail ${badFile}: Invalid PuppyCAD project file: ${badFile}`)
+		expect(stderr[1]).toContain(`fail ${brokenFile}: Invalid JSON in project file ${brokenFile}`)
+
+		const missingOutput = createOutput()
+		const missingCode = await runPuppycadCli(["validate", "--dir", "missing-dir"], { cwd, output: missingOutput.output })
+		expect(missingCode).toBe(2)
+		expect(missingOutput.stderr.join("\n")).toContain("Unable to read project directory")
+	})
+
 	it("writes server-first config keys to the standard JSON shape", async () => {
 		const cwd = await createTempDir()
 		const configPath = join(cwd, "config.json")
@@ -392,6 +556,31 @@ describe("puppycad CLI", () => {
 		expect(JSON.parse(facesOutput.stdout.join("\n"))).toMatchObject({ faces: expect.arrayContaining([expect.objectContaining({ bodyId: "extrude-1-solid" })]) })
 	})
 
+	it("queries feature dependencies and dependents", async () => {
+		const projectId = `cli-refs-test-${crypto.randomUUID()}`
+		createdProjectIds.push(projectId)
+		await persistProject(projectId, createProject(new PCadPart(createPartDocument()).getDocument()))
+
+		const fetch = createServerFetch()
+		const sketchOutput = createOutput()
+		const sketchCode = await runPuppycadCli(["--server-url", "http://server.test", "--json", "query", "refs", projectId, "--target", "sketch-1"], { output: sketchOutput.output, fetch })
+		expect(sketchCode).toBe(0)
+		expect(JSON.parse(sketchOutput.stdout.join("\n"))).toEqual({
+			projectId,
+			refs: [{ id: "sketch-1", partId: "part-1", dependencies: [], dependents: ["extrude-1"] }]
+		})
+
+		const extrudeOutput = createOutput()
+		const extrudeCode = await runPuppycadCli(["--server-url", "http://server.test", "query", "refs", projectId, "--target=extrude-1"], { output: extrudeOutput.output, fetch })
+		expect(extrudeCode).toBe(0)
+		expect(extrudeOutput.stdout).toEqual(["extrude-1 part=part-1 dependencies=sketch-1 dependents=none"])
+
+		const missingOutput = createOutput()
+		const missingCode = await runPuppycadCli(["--server-url", "http://server.test", "query", "refs", projectId, "--target", "extrude-9"], { output: missingOutput.output, fetch })
+		expect(missingCode).toBe(4)
+		expect(missingOutput.stderr).toEqual(["Feature not found: extrude-9"])
+	})
+
 	it("renders a server project preview to a PNG path", async () => {
 		const cwd = await createTempDir()
 		const projectId = `cli-render-test-${crypto.randomUUID()}`
@@ -428,6 +617,189 @@ describe("puppycad CLI", () => {
 		expect(output.stdout.join("\n")).toContain("Rendered")
 		expect(new Uint8Array(await readFile(join(cwd, "preview.png")))).toEqual(pngBytes)
 	})
+
+	it("passes render background colors to the renderer", async () => {
+		const cwd = await createTempDir()
+		const projectId = `cli-render-background-test-${crypto.randomUUID()}`
+		createdProjectIds.push(projectId)
+		await persistProject(projectId, createProject(new PCadPart(createPartDocument()).getDocument()))
+
+		const fetch = createServerFetch()
+		const backgrounds: unknown[] = []
+		const renderPng = async (_bodies: unknown, options: { background?: [number, number, number, number] }) => {
+			backgrounds.push(options.background)
+			return new Uint8Array([137, 80, 78, 71, 13, 10, 26, 10])
+		}
+		const transparentOutput = createOutput()
+		const transparentCode = await runPuppycadCli(["--server-url", "http://server.test", "render", projectId, "--out", "transparent.png", "--background", "transparent"], {
+			cwd,
+			output: transparentOutput.output,
+			fetch,
+			renderPng
+		})
+		const colorOutput = createOutput()
+		const colorCode = await runPuppycadCli(["--server-url", "http://server.test", "render", projectId, "--out", "color.png", "--background=#ff0080"], {
+			cwd,
+			output: colorOutput.output,
+			fetch,
+			renderPng
+		})
+		const invalidOutput = createOutput()
+		const invalidCode = await runPuppycadCli(["--server-url", "http://server.test", "render", projectId, "--out", "invalid.png", "--background", "red"], {
+			cwd,
+			output: invalidOutput.output,
+			fetch,
+			renderPng
+		})
+
+		expect(transparentCode).toBe(0)
+		expect(colorCode).toBe(0)
+		expect(backgrounds[0]).toEqual([0, 0, 0, 0])
+		expect(backgrounds[1]).toEqual([1, 0, 128 / 255, 1])
+		expect(invalidCode).toBe(1)
+		expect(invalidOutput.stderr.join("\n")).toContain("--background requires transparent")
+		expect(backgrounds).toHaveLength(2)
+	})
+
+	it("passes the render projection to the renderer", async () => {
+		const cwd = await createTempDir()
+		const projectId = `cli-render-projection-test-${crypto.randomUUID()}`
+		createdProjectIds.push(projectId)
+		await persistProject(projectId, createProject(new PCadPart(createPartDocument()).getDocument()))
+
+		const fetch = createServerFetch()
+		const projections: unknown[] = []
+		const renderPng = async (_bodies: unknown, options: { projection?: string }) => {
+			projections.push(options.projection)
+			return new Uint8Array([137, 80, 78, 71, 13, 10, 26, 10])
+		}
+		const output = createOutput()
+		const code = await runPuppycadCli(["--server-url", "http://server.test", "render", projectId, "--out", "ortho.png", "--projection", "orthographic"], {
+			cwd,
+			output: output.output,
+			fetch,
+			renderPng
+		})
+		const invalidOutput = createOutput()
+		const invalidCode = await runPuppycadCli(["--server-url", "http://server.test", "render", projectId, "--out", "invalid.png", "--projection=fisheye"], {
+			cwd,
+			output: invalidOutput.output,
+			fetch,
+			renderPng
+		})
+
+		expect(code).toBe(0)
+		expect(projections).toEqual(["orthographic"])
+		expect(invalidCode).toBe(1)
+		expect(invalidOutput.stderr.join("\n")).toContain("Invalid render projection: fisheye")
+	})
+
+	it("renders only the selected bodies and their dependencies", async () => {
+		const cwd = await createTempDir()
+		const projectId = `cli-render-only-test-${crypto.randomUUID()}`
+		createdProjectIds.push(projectId)
+		const partDocument = createPartDocument()
+		const sketch = partDocument.features.find((feature) => feature.type === "sketch")
+		if (sketch?.type === "sketch") {
+			sketch.dimensions = [{ id: "dim-width-1", type: "rectangleWidth", entityId: "rect-1", value: 10 }]
+		}
+		partDocument.features.push(
+			{
+				type: "sketch",
+				id: "sketch-2",
+				name: "Sketch 2",
+				dirty: false,
+				target: { type: "plane", plane: "XY" },
+				entities: [{ id: "rect-2", type: "cornerRectangle", p0: { x: 20, y: 0 }, p1: { x: 30, y: 10 } }],
+				dimensions: [{ id: "dim-width-2", type: "rectangleWidth", entityId: "rect-2", value: 10 }],
+				vertices: [],
+				loops: [],
+				profiles: [{ id: "sketch-2-profile-1", outerLoopId: "loop-1", holeLoopIds: [] }]
+			},
+			{
+				type: "extrude",
+				id: "extrude-2",
+				name: "Extrude 2",
+				target: { type: "profileRef", sketchId: "sketch-2", profileId: "sketch-2-profile-1" },
+				depth: 5
+			},
+			{
+				type: "sketch",
+				id: "sketch-3",
+				name: "Sketch 3",
+				dirty: false,
+				target: { type: "face", face: { type: "extrudeFace", extrudeId: "extrude-2", faceId: "extrude-2-solid-face-6" } },
+				entities: [{ id: "rect-3", type: "cornerRectangle", p0: { x: 2, y: 2 }, p1: { x: 4, y: 4 } }],
+				dimensions: [],
+				vertices: [],
+				loops: [],
+				profiles: [{ id: "sketch-3-profile-1", outerLoopId: "loop-1", holeLoopIds: [] }]
+			},
+			{
+				type: "extrude",
+				id: "extrude-3",
+				name: "Extrude 3",
+				target: { type: "profileRef", sketchId: "sketch-3", profileId: "sketch-3-profile-1" },
+				depth: 2
+			},
+			{
+				type: "extrude",
+				id: "extrude-4",
+				name: "Broken Extrude",
+				target: { type: "profileRef", sketchId: "sketch-1", profileId: "sketch-1-profile-9" },
+				depth: 5
+			}
+		)
+		await persistProject(projectId, createProject(new PCadPart(partDocument).getDocument()))
+
+		const fetch = createServerFetch()
+		const rendered: string[][] = []
+		const labels: { text: string; x: number }[][] = []
+		const renderPng = async (bodies: readonly { id: string }[], options: { labels?: readonly { text: string; position: { x: number } }[] }) => {
+			rendered.push(bodies.map((body) => body.id))
+			labels.push((options.labels ?? []).map((label) => ({ text: label.text, x: label.position.x })))
+			return new Uint8Array([137, 80, 78, 71, 13, 10, 26, 10])
+		}
+		const fullOutput = createOutput()
+		const fullCode = await runPuppycadCli(["--server-url", "http://server.test", "render", projectId, "--out", "full.png"], {
+			cwd,
+			output: fullOutput.output,
+			fetch,
+			renderPng
+		})
+		const output = createOutput()
+		const code = await runPuppycadCli(["--server-url", "http://server.test", "render", projectId, "--out", "subset.png", "--only", "extrude-3", "--show-dimensions"], {
+			cwd,
+			output: output.output,
+			fetch,
+			renderPng
+		})
+		const bothIdsOutput = createOutput()
+		const bothIdsCode = await runPuppycadCli(["--server-url", "http://server.test", "render", projectId, "--out", "both.png", "--only", "extrude-1,extrude-1-solid"], {
+			cwd,
+			output: bothIdsOutput.output,
+			fetch,
+			renderPng
+		})
+		const missingOutput = createOutput()
+		const missingCode = await runPuppycadCli(["--server-url", "http://server.test", "render", projectId, "--out", "missing.png", "--only=extrude-1-solid,extrude-9"], {
+			cwd,
+			output: missingOutput.output,
+			fetch,
+			renderPng
+		})
+
+		expect(fullCode).toBe(4)
+		expect(fullOutput.stderr.join("\n")).toContain("part-1/extrude-4")
+		expect(code).toBe(0)
+		expect(output.stderr).toEqual([])
+		expect(rendered).toEqual([["extrude-2-solid", "extrude-3-solid"], ["extrude-1-solid"]])
+		expect(labels).toEqual([[{ text: "10mm", x: 25 }], []])
+		expect(bothIdsCode).toBe(0)
+		expect(bothIdsOutput.stderr).toEqual([])
+		expect(missingCode).toBe(4)
+		expect(missingOutput.stderr.join("\n")).toContain("Body not found: extrude-9")
+	})
 })
 
 function createServerFetch(): (input: RequestInfo | URL, init?: RequestInit) => Promise<Response> {
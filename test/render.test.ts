@@ -0,0 +1,179 @@
+import { Buffer } from "node:buffer"
+import { chmod, mkdtemp, writeFile } from "node:fs/promises"
+import { tmpdir } from "node:os"
+import { join } from "node:path"
+import zlib from "node:zlib"
+import { afterEach, describe, expect, it } from "bun:test"
+import { extrudeSolidFeature } from "../src/cad/extrude"
+import type { PartProjectItemData } from "../src/contract"
+import { renderProjectPreviewPng, type RenderProjection } from "../src/render"
+
+const originalRenderer = process.env.PUPPYCAD_NODE_RENDERER
+
+afterEach(() => {
+	if (originalRenderer === undefined) {
+		delete process.env.PUPPYCAD_NODE_RENDERER
+	} else {
+		process.env.PUPPYCAD_NODE_RENDERER = originalRenderer
+	}
+})
+
+describe("renderProjectPreviewPng", () => {
+	it("fails cleanly when the render worker exceeds the timeout", async () => {
+		const dir = await mkdtemp(join(tmpdir(), "puppycad-render-"))
+		const stubPath = join(dir, "blocking-renderer.sh")
+		await writeFile(stubPath, "#!/bin/sh\nsleep 30\n", "utf8")
+		await chmod(stubPath, 0o755)
+		process.env.PUPPYCAD_NODE_RENDERER = stubPath
+
+		const started = Date.now()
+		await expect(renderProjectPreviewPng([createCubeBody()], { timeoutMs: 100 })).rejects.toThrow("Headless rendering timed out after 100ms.")
+		expect(Date.now() - started).toBeLessThan(5000)
+	})
+
+	it("keeps transparent backgrounds as zero alpha in the PNG", async () => {
+		const { createContext } = createFakeGl()
+		const png = await renderProjectPreviewPng([createCubeBody()], { width: 8, height: 6, background: [0, 0, 0, 0], createContext })
+
+		const { width, height, colorType, pixels } = decodePng(png)
+		expect(colorType).toBe(6)
+		const corners = [0, width - 1, (height - 1) * width, height * width - 1]
+		expect(corners.map((pixel) => pixels[pixel * 4 + 3])).toEqual([0, 0, 0, 0])
+	})
+
+	it("projects equal boxes at different depths to the same size only under orthographic", async () => {
+		const near = createCubeBody({ x: 0, y: 0 })
+		const far = createCubeBody({ x: 40, y: 40 })
+		const extents = async (projection: RenderProjection) => {
+			const { createContext, uniforms } = createFakeGl()
+			await renderProjectPreviewPng([near, far], { width: 400, height: 300, projection, createContext })
+			const matrix = uniforms.get("modelViewProjection")
+			if (!matrix) {
+				throw new Error("Expected modelViewProjection uniform")
+			}
+			return [projectExtent(near, matrix), projectExtent(far, matrix)] as const
+		}
+
+		const [orthoNear, orthoFar] = await extents("orthographic")
+		expect(orthoFar.width).toBeCloseTo(orthoNear.width, 5)
+		expect(orthoFar.height).toBeCloseTo(orthoNear.height, 5)
+
+		const [perspectiveNear, perspectiveFar] = await extents("perspective")
+		expect(Math.abs(perspectiveFar.width - perspectiveNear.width)).toBeGreaterThan(0.01)
+		expect(Math.abs(perspectiveFar.height - perspectiveNear.height)).toBeGreaterThan(0.01)
+	})
+})
+
+function createFakeGl() {
+	const uniforms = new Map<string, number[]>()
+	let clearColor: [number, number, number, number] = [0, 0, 0, 1]
+	const noop = () => undefined
+	const gl = {
+		createProgram: () => ({}),
+		createShader: () => ({}),
+		createBuffer: () => ({}),
+		getShaderParameter: () => true,
+		getProgramParameter: () => true,
+		getAttribLocation: () => 0,
+		getUniformLocation: (_program: unknown, name: string) => name,
+		uniformMatrix4fv: (location: string, _transpose: boolean, value: Float32Array) => uniforms.set(location, [...value]),
+		clearColor: (red: number, green: number, blue: number, alpha: number) => {
+			clearColor = [red, green, blue, alpha]
+		},
+		readPixels: (_x: number, _y: number, _width: number, _height: number, _format: number, _type: number, pixels: Uint8Array) => {
+			for (let index = 0; index < pixels.length; index += 4) {
+				pixels.set(clearColor.map((channel) => Math.round(channel * 255)), index)
+			}
+		},
+		shaderSource: noop,
+		compileShader: noop,
+		attachShader: noop,
+		linkProgram: noop,
+		useProgram: noop,
+		viewport: noop,
+		clearDepth: noop,
+		enable: noop,
+		cullFace: noop,
+		clear: noop,
+		bindBuffer: noop,
+		bufferData: noop,
+		enableVertexAttribArray: noop,
+		vertexAttribPointer: noop,
+		uniform3f: noop,
+		drawArrays: noop
+	}
+	return { createContext: () => gl as unknown as WebGLRenderingContext, uniforms }
+}
+
+function projectExtent(body: ReturnType<typeof createCubeBody>, matrix: readonly number[]): { width: number; height: number } {
+	const xs: number[] = []
+	const ys: number[] = []
+	for (const { position } of body.vertices) {
+		const project = (index: number) => (matrix[index] ?? 0) * position.x + (matrix[index + 4] ?? 0) * position.y + (matrix[index + 8] ?? 0) * position.z + (matrix[index + 12] ?? 0)
+		const w = project(3)
+		xs.push(project(0) / w)
+		ys.push(project(1) / w)
+	}
+	return { width: Math.max(...xs) - Math.min(...xs), height: Math.max(...ys) - Math.min(...ys) }
+}
+
+function decodePng(png: Uint8Array): { width: number; height: number; colorType: number; pixels: Uint8Array } {
+	const buffer = Buffer.from(png)
+	let offset = 8
+	let width = 0
+	let height = 0
+	let colorType = 0
+	const data: Buffer[] = []
+	while (offset < buffer.length) {
+		const length = buffer.readUInt32BE(offset)
+		const type = buffer.toString("ascii", offset + 4, offset + 8)
+		const chunk = buffer.subarray(offset + 8, offset + 8 + length)
+		if (type === "IHDR") {
+			width = chunk.readUInt32BE(0)
+			height = chunk.readUInt32BE(4)
+			colorType = chunk[9] ?? 0
+		}
+		if (type === "IDAT") {
+			data.push(chunk)
+		}
+		offset += length + 12
+	}
+	const raw = zlib.inflateSync(Buffer.concat(data))
+	const pixels = new Uint8Array(width * height * 4)
+	for (let y = 0; y < height; y += 1) {
+		pixels.set(raw.subarray(y * (width * 4 + 1) + 1, (y + 1) * (width * 4 + 1)), y * width * 4)
+	}
+	return { width, height, colorType, pixels }
+}
+
+function createCubeBody(origin = { x: 0, y: 0 }) {
+	const part: PartProjectItemData = {
+		features: [
+			{
+				type: "sketch",
+				id: "sketch-1",
+				name: "Sketch 1",
+				dirty: false,
+				target: { type: "plane", plane: "XY" },
+				entities: [{ id: "rect-1", type: "cornerRectangle", p0: origin, p1: { x: origin.x + 10, y: origin.y + 10 } }],
+				dimensions: [],
+				vertices: [],
+				loops: [],
+				profiles: [{ id: "sketch-1-profile-1", outerLoopId: "loop-1", holeLoopIds: [] }]
+			},
+			{
+				type: "extrude",
+				id: "extrude-1",
+				name: "Extrude 1",
+				target: { type: "profileRef", sketchId: "sketch-1", profileId: "sketch-1-profile-1" },
+				depth: 10
+			}
+		]
+	}
+	const extrude = part.features.find((feature) => feature.type === "extrude")
+	if (!extrude || extrude.type !== "extrude") {
+		throw new Error("Expected extrude")
+	}
+	const { solid } = extrudeSolidFeature(part, extrude)
+	return { id: solid.id, partId: "part-1", sourceId: solid.featureId, vertices: solid.vertices, edges: solid.edges, faces: solid.faces }
+}
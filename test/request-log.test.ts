@@ -0,0 +1,34 @@
+import { describe, expect, it } from "bun:test"
+import { withRequestLog } from "../src/server/request-log"
+import { getHealth } from "../src/server/save-project"
+
+describe("request log", () => {
+	it("logs one JSON line per request with status and request id", async () => {
+		const lines: string[] = []
+		const handler = withRequestLog(getHealth, (line) => lines.push(line))
+
+		const response = await handler(new Request("http://localhost/health", { headers: { "x-request-id": "req-1" } }))
+
+		expect(response.status).toBe(200)
+		expect(lines).toHaveLength(1)
+		const entry = JSON.parse(lines[0] ?? "{}") as Record<string, unknown>
+		expect(entry).toMatchObject({ method: "GET", path: "/health", status: 200, requestId: "req-1" })
+		expect(typeof entry.durationMs).toBe("number")
+		expect(entry.projectId).toBeUndefined()
+	})
+
+	it("includes the project id from route params and logs failures as 500", async () => {
+		const lines: string[] = []
+		const handler = withRequestLog(
+			async (_request: Request & { params: { projectId: string } }): Promise<Response> => {
+				throw new Error("boom")
+			},
+			(line) => lines.push(line)
+		)
+		const request = Object.assign(new Request("http://localhost/api/projects/bracket", { method: "PUT" }), { params: { projectId: "bracket" } })
+
+		await expect(handler(request)).rejects.toThrow("boom")
+		expect(JSON.parse(lines[0] ?? "{}")).toMatchObject({ method: "PUT", path: "/api/projects/bracket", projectId: "bracket", status: 500 })
+		expect(JSON.parse(lines[0] ?? "{}").requestId).toBeUndefined()
+	})
+})
@@ -33,6 +33,35 @@ describe("server project store", () => {
 		expect(body.project.items[0]).toMatchObject({ id: "part-1", type: "part" })
 	})
 
+	it("GET returns 304 when If-None-Match matches the project ETag", async () => {
+		const projectId = createProjectId()
+		await persistProject(projectId, createProject())
+
+		const first = await getProject(new Request(`http://localhost/api/projects/${projectId}`), projectId)
+		const etag = first.headers.get("etag")
+		expect(etag).toMatch(/^"[0-9a-f]{64}"$/)
+
+		const notModified = await getProject(new Request(`http://localhost/api/projects/${projectId}`, { headers: { "If-None-Match": `W/"stale", ${etag}` } }), projectId)
+		expect(notModified.status).toBe(304)
+		expect(notModified.headers.get("etag")).toBe(etag)
+		expect(await notModified.text()).toBe("")
+
+		const stale = await getProject(new Request(`http://localhost/api/projects/${projectId}`, { headers: { "If-None-Match": '"stale"' } }), projectId)
+		expect(stale.status).toBe(200)
+		expect(((await stale.json()) as { project: Project }).project.items[0]).toMatchObject({ id: "part-1" })
+
+		await postProjectCommands(
+			new Request(`http://localhost/api/projects/${projectId}/commands`, {
+				method: "POST",
+				body: JSON.stringify({ clientId: "client-a", baseRevision: 0, commands: [{ type: "renameNode", nodeId: "part-1", name: "Renamed" }] })
+			}),
+			projectId
+		)
+		const changed = await getProject(new Request(`http://localhost/api/projects/${projectId}`, { headers: { "If-None-Match": etag ?? "" } }), projectId)
+		expect(changed.status).toBe(200)
+		expect(changed.headers.get("etag")).not.toBe(etag)
+	})
+
 	it("POST commands persists the canonical project and increments revision", async () => {
 		const projectId = createProjectId()
 		await persistProject(projectId, createProject())
@@ -0,0 +1,42 @@
+import { describe, expect, it } from "bun:test"
+import { extrudeSolidFeature } from "../src/cad/extrude"
+import { PCadPart } from "../src/pcad/project"
+import { normalizeProjectFile } from "../src/project-file"
+import type { PartDocument } from "../src/schema"
+import { getTemplate, getTemplates } from "../src/server/templates"
+
+describe("server project templates", () => {
+	it("lists the bundled starter templates", async () => {
+		const response = await getTemplates(new Request("http://localhost/api/templates"))
+		expect(response.status).toBe(200)
+		const body = (await response.json()) as { ok: boolean; templates: { name: string; description: string }[] }
+		expect(body.ok).toBe(true)
+		expect(body.templates.length).toBeGreaterThan(0)
+		expect(body.templates.map((template) => template.name)).toContain("box")
+	})
+
+	it("returns template project files that load and build", async () => {
+		const response = await getTemplate(new Request("http://localhost/api/templates/bracket"), "bracket")
+		expect(response.status).toBe(200)
+		const project = normalizeProjectFile(JSON.parse(await response.text()))
+		if (!project) {
+			throw new Error("Expected a valid project file")
+		}
+		const part = project.items[0]
+		if (!part || !("type" in part) || part.type !== "part") {
+			throw new Error("Expected a part document")
+		}
+		const document = new PCadPart(part.data).getDocument() as PartDocument
+		const extrudes = document.features.filter((feature) => feature.type === "extrude")
+		expect(extrudes).toHaveLength(2)
+		for (const extrude of extrudes) {
+			expect(extrudeSolidFeature(document, extrude).solid.faces).toHaveLength(6)
+		}
+	})
+
+	it("returns 404 for unknown templates", async () => {
+		const response = await getTemplate(new Request("http://localhost/api/templates/missing"), "missing")
+		expect(response.status).toBe(404)
+		expect(await response.json()).toMatchObject({ ok: false, code: "not_found" })
+	})
+})